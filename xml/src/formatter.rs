@@ -2,10 +2,12 @@ use crate::model::OwnedNamedOrBlankNode;
 use crate::utils::*;
 use crate::RdfXmlError;
 use quick_xml::events::*;
-use quick_xml::Writer;
+use quick_xml::{Reader, Writer};
 use rio_api::formatter::TriplesFormatter;
 use rio_api::model::*;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
+use std::iter::FromIterator;
 
 /// A [RDF XML](https://www.w3.org/TR/rdf-syntax-grammar/) formatter.
 ///
@@ -28,101 +30,663 @@ use std::io::Write;
 pub struct RdfXmlFormatter<W: Write> {
     writer: Writer<W>,
     current_subject: Option<OwnedNamedOrBlankNode>,
+    buffer: Vec<BufferedProperty>,
+    graph: Vec<OwnedTriple>,
+    prefixes: PrefixMap,
+    abbreviate_types: bool,
+    collapse_collections: bool,
+    started: bool,
 }
 
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDF_XML_LITERAL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#XMLLiteral";
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+
 impl<W: Write> RdfXmlFormatter<W> {
     /// Builds a new formatter from a `Write` implementation and starts writing
     pub fn new(write: W) -> Result<Self, RdfXmlError> {
-        let mut writer = Writer::new(write);
-        writer.write_event(Event::Decl(BytesDecl::new(b"1.0", Some(b"UTF-8"), None)))?;
-        let mut rdf_open = BytesStart::borrowed_name(b"rdf:RDF");
-        rdf_open.push_attribute(("xmlns:rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"));
-        writer.write_event(Event::Start(rdf_open))?;
+        Self::with_prefixes(write, std::iter::empty())
+    }
+
+    /// Builds a new formatter that declares the given `prefix`&#8594;`namespace` bindings once
+    /// on the root `rdf:RDF` element.
+    ///
+    /// Property elements whose predicate namespace matches one of these bindings are written as
+    /// qualified `prefix:local` names without an inline `xmlns` redeclaration. Predicates whose
+    /// namespace is not registered keep their per-element declaration.
+    ///
+    /// ```
+    /// use rio_xml::RdfXmlFormatter;
+    /// use rio_api::formatter::TriplesFormatter;
+    /// use rio_api::model::{NamedNode, Triple};
+    ///
+    /// let mut formatter = RdfXmlFormatter::with_prefixes(
+    ///     Vec::default(),
+    ///     vec![("schema".to_owned(), "http://schema.org/".to_owned())],
+    /// ).unwrap();
+    /// formatter.format(&Triple {
+    ///     subject: NamedNode { iri: "http://example.com/foo" }.into(),
+    ///     predicate: NamedNode { iri: "http://schema.org/name" }.into(),
+    ///     object: NamedNode { iri: "http://schema.org/Person" }.into()
+    /// }).unwrap();
+    /// let _xml = formatter.finish().unwrap();
+    /// ```
+    pub fn with_prefixes(
+        write: W,
+        prefixes: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self, RdfXmlError> {
         Ok(Self {
-            writer,
+            writer: Writer::new(write),
             current_subject: None,
+            buffer: Vec::new(),
+            graph: Vec::new(),
+            prefixes: prefixes.into_iter().collect(),
+            abbreviate_types: false,
+            collapse_collections: false,
+            started: false,
         })
     }
 
+    /// Enables or disables the RDF collection abbreviation.
+    ///
+    /// When enabled, a well-formed RDF list (a chain of blank nodes each carrying exactly one
+    /// `rdf:first` and one `rdf:rest`, terminating at `rdf:nil`, referenced exactly once) is
+    /// collapsed into `<prop rdf:parseType="Collection">`&#8230;`</prop>` with the members nested
+    /// as child elements. Chains that branch, are shared, or are referenced more than once fall
+    /// back to the expanded `rdf:first`/`rdf:rest` form so the triples are preserved exactly.
+    ///
+    /// This mode trades streaming for nicer output: because the whole list must be seen before it
+    /// can be recognized, every triple is accumulated in memory and only written at `finish`.
+    pub fn with_collection_abbreviation(mut self, enabled: bool) -> Self {
+        self.collapse_collections = enabled;
+        self
+    }
+
+    /// Enables or disables the typed-node abbreviation.
+    ///
+    /// When enabled a subject with a single named `rdf:type` is emitted as a typed-node element
+    /// (`<schema:Person rdf:about="...">`) instead of an `rdf:Description` with an explicit
+    /// `rdf:type` child. It is disabled by default so the flat `rdf:Description` output of
+    /// `new`/`with_prefixes` is preserved for existing callers.
+    pub fn with_type_abbreviation(mut self, enabled: bool) -> Self {
+        self.abbreviate_types = enabled;
+        self
+    }
+
+    /// Writes the XML declaration and the opening `rdf:RDF` element with every registered prefix.
+    ///
+    /// This is deferred until the first triple (or `finish`) so that the whole prefix map is known
+    /// by the time the root element is emitted.
+    fn write_start(&mut self) -> Result<(), RdfXmlError> {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
+        self.writer
+            .write_event(Event::Decl(BytesDecl::new(b"1.0", Some(b"UTF-8"), None)))?;
+        let mut rdf_open = BytesStart::borrowed_name(b"rdf:RDF");
+        rdf_open.push_attribute(("xmlns:rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"));
+        for (prefix, namespace) in &self.prefixes.bindings {
+            rdf_open.push_attribute((format!("xmlns:{}", prefix).as_str(), namespace.as_str()));
+        }
+        self.writer.write_event(Event::Start(rdf_open))?;
+        Ok(())
+    }
+
     /// Finishes to write and returns the underlying `Write`
     pub fn finish(mut self) -> Result<W, RdfXmlError> {
-        if self.current_subject.is_some() {
-            self.writer
-                .write_event(Event::End(BytesEnd::borrowed(b"rdf:Description")))?;
+        self.write_start()?;
+        if self.collapse_collections {
+            self.flush_graph()?;
         }
+        self.flush_subject()?;
         self.writer
             .write_event(Event::End(BytesEnd::borrowed(b"rdf:RDF")))?;
         Ok(self.writer.into_inner())
     }
-}
 
-impl<W: Write> TriplesFormatter for RdfXmlFormatter<W> {
-    type Error = RdfXmlError;
+    /// Writes the staged triples of the current subject, then clears the buffer.
+    ///
+    /// A subject with exactly one named `rdf:type` is collapsed into a typed-node element (unless
+    /// the abbreviation is disabled or the type namespace has no usable local name); otherwise a
+    /// plain `rdf:Description` is emitted.
+    fn flush_subject(&mut self) -> Result<(), RdfXmlError> {
+        let subject = match self.current_subject.take() {
+            Some(subject) => subject,
+            None => return Ok(()),
+        };
+        let properties = std::mem::take(&mut self.buffer);
 
-    fn format(&mut self, triple: &Triple<'_>) -> Result<(), RdfXmlError> {
-        // We open a new rdf:Description if useful
-        if self.current_subject.as_ref().map(|v| v.into()) != Some(triple.subject) {
-            if self.current_subject.is_some() {
-                self.writer
-                    .write_event(Event::End(BytesEnd::borrowed(b"rdf:Description")))?;
+        // A usable typed node needs a single `rdf:type` pointing at a named node whose IRI splits
+        // into a non-empty local name.
+        let type_node = if self.abbreviate_types {
+            let mut types = properties
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.predicate == RDF_TYPE);
+            match (types.next(), types.next()) {
+                (Some((index, property)), None) => match &property.object {
+                    BufferedObject::NamedNode(iri) => {
+                        self.resolve_type_name(iri).map(|name| (index, name))
+                    }
+                    _ => None,
+                },
+                _ => None,
             }
+        } else {
+            None
+        };
+
+        let subject_ref: NamedOrBlankNode<'_> = (&subject).into();
+        let (subject_key, subject_value) = match subject_ref {
+            NamedOrBlankNode::NamedNode(n) => ("rdf:about", n.iri),
+            NamedOrBlankNode::BlankNode(n) => ("rdf:nodeID", n.id),
+        };
+
+        let (element, skip_index) = match &type_node {
+            Some((index, name)) => (name.clone(), Some(*index)),
+            None => (
+                ResolvedName {
+                    qname: "rdf:Description".to_owned(),
+                    xmlns: None,
+                },
+                None,
+            ),
+        };
+
+        let has_children = properties
+            .iter()
+            .enumerate()
+            .any(|(i, _)| Some(i) != skip_index);
 
-            let mut description_open = BytesStart::borrowed_name(b"rdf:Description");
-            match triple.subject {
-                NamedOrBlankNode::NamedNode(n) => {
-                    description_open.push_attribute(("rdf:about", n.iri))
+        let mut open = BytesStart::owned_name(element.qname.as_bytes());
+        if let Some((key, value)) = &element.xmlns {
+            open.push_attribute((key.as_str(), value.as_str()));
+        }
+        open.push_attribute((subject_key, subject_value));
+
+        if has_children {
+            self.writer.write_event(Event::Start(open))?;
+            for (index, property) in properties.iter().enumerate() {
+                if Some(index) == skip_index {
+                    continue;
                 }
-                NamedOrBlankNode::BlankNode(n) => {
-                    description_open.push_attribute(("rdf:nodeID", n.id))
+                self.write_property(property)?;
+            }
+            self.writer
+                .write_event(Event::End(BytesEnd::borrowed(element.qname.as_bytes())))?;
+        } else {
+            self.writer.write_event(Event::Empty(open))?;
+        }
+        Ok(())
+    }
+
+    /// Resolves the accumulated graph, collapsing recognized RDF lists, and replays it through the
+    /// per-subject staging machinery.
+    fn flush_graph(&mut self) -> Result<(), RdfXmlError> {
+        let graph = std::mem::take(&mut self.graph);
+        let (collections, suppressed) = detect_collections(&graph);
+
+        for (index, triple) in graph.iter().enumerate() {
+            let subject_ref: NamedOrBlankNode<'_> = (&triple.subject).into();
+            // Skip the triples that make up a collapsed list chain; they are re-emitted as nested
+            // members of the owning property.
+            if let NamedOrBlankNode::BlankNode(n) = subject_ref {
+                if suppressed.contains(n.id) {
+                    continue;
                 }
             }
-            self.writer.write_event(Event::Start(description_open))?;
+
+            if self.current_subject.as_ref().map(|v| v.into()) != Some(subject_ref) {
+                self.flush_subject()?;
+                self.current_subject = Some(triple.subject.clone());
+            }
+
+            self.buffer.push(BufferedProperty {
+                predicate: triple.predicate.clone(),
+                object: triple.object.clone(),
+                collection: collections.get(&index).cloned(),
+            });
         }
+        self.flush_subject()
+    }
 
-        let (prop_prefix, prop_value) = split_iri(triple.predicate.iri);
-        let (prop_qname, prop_xmlns) = if prop_value.is_empty() {
-            ("prop:", ("xmlns:prop", prop_prefix))
-        } else {
-            (prop_value, ("xmlns", prop_prefix))
-        };
-        let mut property_open = BytesStart::borrowed_name(prop_qname.as_bytes());
-        property_open.push_attribute(prop_xmlns);
-        let content = match triple.object {
-            Term::NamedNode(n) => {
-                property_open.push_attribute(("rdf:resource", n.iri));
+    /// Resolves the predicate IRI into a property element name, reusing a registered prefix when
+    /// possible and otherwise declaring the namespace inline.
+    fn resolve_property_name(&self, predicate: &str) -> ResolvedName {
+        let (namespace, local) = split_iri(predicate);
+        match self.prefixes.prefix_for(namespace) {
+            Some(prefix) if !local.is_empty() => ResolvedName {
+                qname: format!("{}:{}", prefix, local),
+                xmlns: None,
+            },
+            _ if local.is_empty() => ResolvedName {
+                qname: "prop:".to_owned(),
+                xmlns: Some(("xmlns:prop".to_owned(), namespace.to_owned())),
+            },
+            _ => ResolvedName {
+                qname: local.to_owned(),
+                xmlns: Some(("xmlns".to_owned(), namespace.to_owned())),
+            },
+        }
+    }
+
+    /// Resolves a class IRI into a typed-node element name, or `None` if it has no usable local
+    /// name to turn into an element.
+    fn resolve_type_name(&self, iri: &str) -> Option<ResolvedName> {
+        let (namespace, local) = split_iri(iri);
+        if local.is_empty() {
+            return None;
+        }
+        Some(match self.prefixes.prefix_for(namespace) {
+            Some(prefix) => ResolvedName {
+                qname: format!("{}:{}", prefix, local),
+                xmlns: None,
+            },
+            None => ResolvedName {
+                qname: local.to_owned(),
+                xmlns: Some(("xmlns".to_owned(), namespace.to_owned())),
+            },
+        })
+    }
+
+    /// Writes a single staged property as a child element of the current subject.
+    fn write_property(&mut self, property: &BufferedProperty) -> Result<(), RdfXmlError> {
+        let name = self.resolve_property_name(&property.predicate);
+        let mut open = BytesStart::owned_name(name.qname.as_bytes());
+        if let Some((key, value)) = &name.xmlns {
+            open.push_attribute((key.as_str(), value.as_str()));
+        }
+
+        // A recognized RDF list is written as `rdf:parseType="Collection"` with its members nested
+        // as node elements rather than the blank-node chain.
+        if let Some(members) = &property.collection {
+            open.push_attribute(("rdf:parseType", "Collection"));
+            self.writer.write_event(Event::Start(open))?;
+            for member in members {
+                let mut node = BytesStart::borrowed_name(b"rdf:Description");
+                match member {
+                    BufferedObject::NamedNode(iri) => node.push_attribute(("rdf:about", iri.as_str())),
+                    BufferedObject::BlankNode(id) => node.push_attribute(("rdf:nodeID", id.as_str())),
+                    // Non-resource members cannot appear in a Collection; such lists are never
+                    // collapsed, so this arm is unreachable.
+                    _ => unreachable!("collection members are always resources"),
+                }
+                self.writer.write_event(Event::Empty(node))?;
+            }
+            self.writer
+                .write_event(Event::End(BytesEnd::borrowed(name.qname.as_bytes())))?;
+            return Ok(());
+        }
+
+        // An XMLLiteral is written with `rdf:parseType="Literal"` and its value emitted as raw,
+        // unescaped markup so embedded XML is preserved rather than corrupted.
+        if let BufferedObject::Typed { value, datatype } = &property.object {
+            if datatype == RDF_XML_LITERAL {
+                validate_xml_literal(value)?;
+                open.push_attribute(("rdf:parseType", "Literal"));
+                self.writer.write_event(Event::Start(open))?;
+                self.writer
+                    .write_event(Event::Text(BytesText::from_escaped_str(value.as_str())))?;
+                self.writer
+                    .write_event(Event::End(BytesEnd::borrowed(name.qname.as_bytes())))?;
+                return Ok(());
+            }
+        }
+
+        let content = match &property.object {
+            BufferedObject::NamedNode(iri) => {
+                open.push_attribute(("rdf:resource", iri.as_str()));
                 None
             }
-            Term::BlankNode(n) => {
-                property_open.push_attribute(("rdf:nodeID", n.id));
+            BufferedObject::BlankNode(id) => {
+                open.push_attribute(("rdf:nodeID", id.as_str()));
                 None
             }
-            Term::Literal(l) => match l {
-                Literal::Simple { value } => Some(value),
-                Literal::LanguageTaggedString { value, language } => {
-                    property_open.push_attribute(("xml:lang", language));
-                    Some(value)
-                }
-                Literal::Typed { value, datatype } => {
-                    property_open.push_attribute(("rdf:datatype", datatype.iri));
-                    Some(value)
-                }
-            },
+            BufferedObject::Simple(value) => Some(value.as_str()),
+            BufferedObject::LanguageTagged { value, language } => {
+                open.push_attribute(("xml:lang", language.as_str()));
+                Some(value.as_str())
+            }
+            BufferedObject::Typed { value, datatype } => {
+                open.push_attribute(("rdf:datatype", datatype.as_str()));
+                Some(value.as_str())
+            }
         };
         if let Some(content) = content {
-            self.writer.write_event(Event::Start(property_open))?;
+            self.writer.write_event(Event::Start(open))?;
             self.writer
-                .write_event(Event::Text(BytesText::from_plain_str(&content)))?;
+                .write_event(Event::Text(BytesText::from_plain_str(content)))?;
             self.writer
-                .write_event(Event::End(BytesEnd::borrowed(prop_qname.as_bytes())))?;
+                .write_event(Event::End(BytesEnd::borrowed(name.qname.as_bytes())))?;
         } else {
-            self.writer.write_event(Event::Empty(property_open))?;
+            self.writer.write_event(Event::Empty(open))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl RdfXmlFormatter<Vec<u8>> {
+    /// Mutable access to the in-memory buffer backing an async formatter, so already-serialized
+    /// bytes can be drained to the async writer between events.
+    ///
+    /// `Writer::inner` is the pinned quick-xml 0.22 accessor for the wrapped writer.
+    pub(crate) fn buffer_mut(&mut self) -> &mut Vec<u8> {
+        self.writer.inner()
+    }
+}
+
+/// An async ([tokio](https://tokio.rs/)) counterpart of [`RdfXmlFormatter`].
+///
+/// It mirrors the synchronous API (`new`/`format`/`finish`) but writes into any
+/// [`AsyncWrite`](tokio::io::AsyncWrite). Internally the events are serialized into an in-memory
+/// buffer by a shared synchronous [`RdfXmlFormatter`], whose bytes are drained and awaited after
+/// each call, so all `split_iri`/namespace/abbreviation logic is shared with the sync path.
+///
+/// It is only available when the `async-tokio` feature is enabled.
+#[cfg(feature = "async-tokio")]
+pub struct AsyncRdfXmlFormatter<W: tokio::io::AsyncWrite + Unpin> {
+    writer: W,
+    inner: RdfXmlFormatter<Vec<u8>>,
+}
+
+#[cfg(feature = "async-tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin> AsyncRdfXmlFormatter<W> {
+    /// Builds a new formatter from an `AsyncWrite` implementation.
+    ///
+    /// Nothing is written yet: the XML declaration and root element are emitted with the first
+    /// triple (or at `finish`).
+    pub async fn new(writer: W) -> Result<Self, RdfXmlError> {
+        Self::with_prefixes(writer, std::iter::empty()).await
+    }
+
+    /// Builds a new formatter declaring the given `prefix`&#8594;`namespace` bindings, mirroring
+    /// [`RdfXmlFormatter::with_prefixes`].
+    pub async fn with_prefixes(
+        mut writer: W,
+        prefixes: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self, RdfXmlError> {
+        let mut inner = RdfXmlFormatter::with_prefixes(Vec::new(), prefixes)?;
+        drain(&mut writer, &mut inner).await?;
+        Ok(Self { writer, inner })
+    }
+
+    /// Enables or disables the typed-node abbreviation, mirroring
+    /// [`RdfXmlFormatter::with_type_abbreviation`].
+    pub fn with_type_abbreviation(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_type_abbreviation(enabled);
+        self
+    }
+
+    /// Writes a triple, awaiting the flush of any bytes it produced
+    pub async fn format(&mut self, triple: &Triple<'_>) -> Result<(), RdfXmlError> {
+        self.inner.format(triple)?;
+        drain(&mut self.writer, &mut self.inner).await
+    }
+
+    /// Finishes to write and returns the underlying `AsyncWrite`
+    pub async fn finish(self) -> Result<W, RdfXmlError> {
+        use tokio::io::AsyncWriteExt;
+        let Self { mut writer, inner } = self;
+        let remaining = inner.finish()?;
+        if !remaining.is_empty() {
+            writer.write_all(&remaining).await?;
+        }
+        writer.flush().await?;
+        Ok(writer)
+    }
+}
+
+/// Flushes the sync formatter's in-memory buffer to the async writer and clears it.
+#[cfg(feature = "async-tokio")]
+async fn drain<W: tokio::io::AsyncWrite + Unpin>(
+    writer: &mut W,
+    inner: &mut RdfXmlFormatter<Vec<u8>>,
+) -> Result<(), RdfXmlError> {
+    use tokio::io::AsyncWriteExt;
+    let buffer = inner.buffer_mut();
+    if !buffer.is_empty() {
+        writer.write_all(buffer.as_slice()).await?;
+        buffer.clear();
+    }
+    Ok(())
+}
+
+/// A resolved element name together with an optional inline `xmlns` declaration to emit with it.
+#[derive(Clone)]
+struct ResolvedName {
+    qname: String,
+    xmlns: Option<(String, String)>,
+}
+
+/// A subject's property (predicate IRI plus object) staged until the subject is flushed.
+struct BufferedProperty {
+    predicate: String,
+    object: BufferedObject,
+    /// Present when the object is a collapsed RDF list; holds its members in order.
+    collection: Option<Vec<BufferedObject>>,
+}
+
+/// An owned triple accumulated in the graph buffer while collapsing collections.
+struct OwnedTriple {
+    subject: OwnedNamedOrBlankNode,
+    predicate: String,
+    object: BufferedObject,
+}
+
+/// An owned copy of an object term, kept alive across the per-subject staging buffer.
+#[derive(Clone)]
+enum BufferedObject {
+    NamedNode(String),
+    BlankNode(String),
+    Simple(String),
+    LanguageTagged { value: String, language: String },
+    Typed { value: String, datatype: String },
+}
+
+impl BufferedObject {
+    fn from_term(term: Term<'_>) -> Self {
+        match term {
+            Term::NamedNode(n) => BufferedObject::NamedNode(n.iri.to_owned()),
+            Term::BlankNode(n) => BufferedObject::BlankNode(n.id.to_owned()),
+            Term::Literal(Literal::Simple { value }) => BufferedObject::Simple(value.to_owned()),
+            Term::Literal(Literal::LanguageTaggedString { value, language }) => {
+                BufferedObject::LanguageTagged {
+                    value: value.to_owned(),
+                    language: language.to_owned(),
+                }
+            }
+            Term::Literal(Literal::Typed { value, datatype }) => BufferedObject::Typed {
+                value: value.to_owned(),
+                datatype: datatype.iri.to_owned(),
+            },
+        }
+    }
+}
+
+/// A set of `prefix`&#8594;`namespace` bindings declared once on the root element.
+#[derive(Default)]
+struct PrefixMap {
+    bindings: Vec<(String, String)>,
+}
+
+impl PrefixMap {
+    /// Returns the prefix bound to `namespace`, if any.
+    fn prefix_for(&self, namespace: &str) -> Option<&str> {
+        self.bindings
+            .iter()
+            .find(|(_, ns)| ns == namespace)
+            .map(|(prefix, _)| prefix.as_str())
+    }
+}
+
+impl FromIterator<(String, String)> for PrefixMap {
+    fn from_iter<T: IntoIterator<Item = (String, String)>>(iter: T) -> Self {
+        Self {
+            bindings: iter.into_iter().collect(),
         }
+    }
+}
 
-        self.current_subject = Some(triple.subject.into());
+impl<W: Write> TriplesFormatter for RdfXmlFormatter<W> {
+    type Error = RdfXmlError;
+
+    fn format(&mut self, triple: &Triple<'_>) -> Result<(), RdfXmlError> {
+        // Collapsing collections needs to see every triple before any list can be recognized, so
+        // in that mode we accumulate the whole graph and resolve it at `finish`.
+        if self.collapse_collections {
+            self.graph.push(OwnedTriple {
+                subject: triple.subject.into(),
+                predicate: triple.predicate.iri.to_owned(),
+                object: BufferedObject::from_term(triple.object),
+            });
+            return Ok(());
+        }
+
+        self.write_start()?;
+
+        // Triples arrive grouped by subject: flush the staged one when the subject changes, then
+        // stage this triple for the (possibly abbreviated) element written on the next flush.
+        if self.current_subject.as_ref().map(|v| v.into()) != Some(triple.subject) {
+            self.flush_subject()?;
+            self.current_subject = Some(triple.subject.into());
+        }
+
+        self.buffer.push(BufferedProperty {
+            predicate: triple.predicate.iri.to_owned(),
+            object: BufferedObject::from_term(triple.object),
+            collection: None,
+        });
         Ok(())
     }
 }
 
+/// Per-blank-node bookkeeping used to recognize RDF list cells.
+#[derive(Default)]
+struct CellInfo<'a> {
+    /// Number of times the node appears as an object across the whole graph.
+    references: usize,
+    /// Objects of its `rdf:first` triples (a list cell has exactly one).
+    firsts: Vec<&'a BufferedObject>,
+    /// Objects of its `rdf:rest` triples (a list cell has exactly one).
+    rests: Vec<&'a BufferedObject>,
+    /// Count of any other predicate on the node (a list cell has none).
+    others: usize,
+}
+
+/// Scans the accumulated graph for well-formed RDF lists.
+///
+/// Returns the collections to emit, keyed by the index of the triple that references each list
+/// head, together with the set of blank-node ids that make up collapsed chains (whose own triples
+/// must be suppressed). Any chain that branches, is shared, is referenced more than once, or whose
+/// members are not resources is left out so the expanded form is preserved.
+fn detect_collections(
+    graph: &[OwnedTriple],
+) -> (HashMap<usize, Vec<BufferedObject>>, HashSet<String>) {
+    let mut cells: HashMap<&str, CellInfo<'_>> = HashMap::new();
+    for triple in graph {
+        if let BufferedObject::BlankNode(id) = &triple.object {
+            cells.entry(id.as_str()).or_default().references += 1;
+        }
+        let subject_ref: NamedOrBlankNode<'_> = (&triple.subject).into();
+        if let NamedOrBlankNode::BlankNode(n) = subject_ref {
+            let cell = cells.entry(n.id).or_default();
+            match triple.predicate.as_str() {
+                RDF_FIRST => cell.firsts.push(&triple.object),
+                RDF_REST => cell.rests.push(&triple.object),
+                _ => cell.others += 1,
+            }
+        }
+    }
+
+    let mut collections = HashMap::new();
+    let mut suppressed = HashSet::new();
+    for (index, triple) in graph.iter().enumerate() {
+        // A list head is referenced by a genuine property, never by a chain-internal link.
+        if triple.predicate == RDF_FIRST || triple.predicate == RDF_REST {
+            continue;
+        }
+        if let BufferedObject::BlankNode(id) = &triple.object {
+            if let Some((members, chain)) = collect_list(id.as_str(), &cells) {
+                collections.insert(index, members);
+                suppressed.extend(chain);
+            }
+        }
+    }
+    (collections, suppressed)
+}
+
+/// Walks the `rdf:first`/`rdf:rest` chain starting at `head`, returning the ordered members and the
+/// ids of every cell in the chain, or `None` if the structure is not a collapsible list.
+fn collect_list(
+    head: &str,
+    cells: &HashMap<&str, CellInfo<'_>>,
+) -> Option<(Vec<BufferedObject>, Vec<String>)> {
+    let mut members = Vec::new();
+    let mut chain = Vec::new();
+    let mut node = head.to_owned();
+    loop {
+        let cell = cells.get(node.as_str())?;
+        if cell.references != 1 || cell.firsts.len() != 1 || cell.rests.len() != 1 || cell.others > 0
+        {
+            return None;
+        }
+        // Collection members must be resources; a literal member is not expressible this way.
+        let member = match cell.firsts[0] {
+            BufferedObject::NamedNode(iri) => BufferedObject::NamedNode(iri.clone()),
+            BufferedObject::BlankNode(id) => BufferedObject::BlankNode(id.clone()),
+            _ => return None,
+        };
+        members.push(member);
+        chain.push(node.clone());
+        match cell.rests[0] {
+            BufferedObject::NamedNode(iri) if iri == RDF_NIL => return Some((members, chain)),
+            BufferedObject::BlankNode(next) => {
+                if chain.iter().any(|n| n == next) {
+                    return None; // cycle
+                }
+                node = next.clone();
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Checks that an XMLLiteral value is a well-formed XML fragment before it is written as raw
+/// markup, returning the underlying parse error otherwise.
+///
+/// The reader only validates tag structure, so text nodes are additionally unescaped to reject a
+/// bare `&`, and every opened element must be closed by the end of the fragment to reject a bare
+/// `<` (which the reader takes as the start of a tag); either would otherwise be emitted verbatim
+/// into an invalid document.
+fn validate_xml_literal(value: &str) -> Result<(), RdfXmlError> {
+    let wrapped = format!("<rdf:XMLLiteral>{}</rdf:XMLLiteral>", value);
+    let mut reader = Reader::from_str(&wrapped);
+    let mut buffer = Vec::new();
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event(&mut buffer)? {
+            Event::Eof => {
+                if depth != 0 {
+                    return Err(quick_xml::Error::UnexpectedEof("XMLLiteral".to_owned()).into());
+                }
+                break;
+            }
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Text(text) => {
+                text.unescaped()?;
+            }
+            _ => {}
+        }
+        buffer.clear();
+    }
+    Ok(())
+}
+
 fn split_iri(iri: &str) -> (&str, &str) {
     if let Some(position_base) = iri.rfind(|c| !is_name_char(c)) {
         if let Some(position_add) = iri[position_base..].find(is_name_start_char) {
@@ -146,3 +710,334 @@ fn test_split_iri() {
     );
     assert_eq!(split_iri("http://schema.org/"), ("http://schema.org/", ""));
 }
+
+#[cfg(test)]
+fn format_all(triples: &[Triple<'_>], abbreviate_types: bool) -> String {
+    let mut formatter = RdfXmlFormatter::new(Vec::default())
+        .unwrap()
+        .with_type_abbreviation(abbreviate_types);
+    for triple in triples {
+        formatter.format(triple).unwrap();
+    }
+    String::from_utf8(formatter.finish().unwrap()).unwrap()
+}
+
+#[test]
+fn test_typed_node_abbreviation() {
+    let xml = format_all(
+        &[Triple {
+            subject: NamedNode {
+                iri: "http://example.com/foo",
+            }
+            .into(),
+            predicate: NamedNode {
+                iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#type",
+            }
+            .into(),
+            object: NamedNode {
+                iri: "http://schema.org/Person",
+            }
+            .into(),
+        }],
+        true,
+    );
+    assert!(xml.contains("<Person"));
+    assert!(xml.contains("rdf:about=\"http://example.com/foo\""));
+    assert!(!xml.contains("rdf:type"));
+}
+
+#[test]
+fn test_multiple_types_fall_back_to_description() {
+    let type_iri = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+    let xml = format_all(
+        &[
+            Triple {
+                subject: NamedNode {
+                    iri: "http://example.com/foo",
+                }
+                .into(),
+                predicate: NamedNode { iri: type_iri }.into(),
+                object: NamedNode {
+                    iri: "http://schema.org/Person",
+                }
+                .into(),
+            },
+            Triple {
+                subject: NamedNode {
+                    iri: "http://example.com/foo",
+                }
+                .into(),
+                predicate: NamedNode { iri: type_iri }.into(),
+                object: NamedNode {
+                    iri: "http://schema.org/Agent",
+                }
+                .into(),
+            },
+        ],
+        true,
+    );
+    assert!(xml.contains("<rdf:Description"));
+    assert!(!xml.contains("<Person"));
+}
+
+#[test]
+fn test_empty_local_type_falls_back_to_description() {
+    let xml = format_all(
+        &[Triple {
+            subject: NamedNode {
+                iri: "http://example.com/foo",
+            }
+            .into(),
+            predicate: NamedNode {
+                iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#type",
+            }
+            .into(),
+            object: NamedNode {
+                iri: "http://example.com/",
+            }
+            .into(),
+        }],
+        true,
+    );
+    assert!(xml.contains("<rdf:Description"));
+}
+
+#[test]
+fn test_xml_literal_is_written_raw() {
+    let xml = format_all(
+        &[Triple {
+            subject: NamedNode {
+                iri: "http://example.com/foo",
+            }
+            .into(),
+            predicate: NamedNode {
+                iri: "http://example.com/markup",
+            }
+            .into(),
+            object: Literal::Typed {
+                value: "<b>bold</b>",
+                datatype: NamedNode {
+                    iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#XMLLiteral",
+                },
+            }
+            .into(),
+        }],
+        false,
+    );
+    assert!(xml.contains("rdf:parseType=\"Literal\""));
+    assert!(xml.contains("<b>bold</b>"));
+    assert!(!xml.contains("&lt;b&gt;"));
+}
+
+#[test]
+fn test_malformed_xml_literal_is_rejected() {
+    let mut formatter = RdfXmlFormatter::new(Vec::default()).unwrap();
+    formatter
+        .format(&Triple {
+            subject: NamedNode {
+                iri: "http://example.com/foo",
+            }
+            .into(),
+            predicate: NamedNode {
+                iri: "http://example.com/markup",
+            }
+            .into(),
+            object: Literal::Typed {
+                value: "<a></b>",
+                datatype: NamedNode {
+                    iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#XMLLiteral",
+                },
+            }
+            .into(),
+        })
+        .unwrap();
+    assert!(formatter.finish().is_err());
+}
+
+#[test]
+fn test_xml_literal_with_unescaped_entity_is_rejected() {
+    let mut formatter = RdfXmlFormatter::new(Vec::default()).unwrap();
+    formatter
+        .format(&Triple {
+            subject: NamedNode {
+                iri: "http://example.com/foo",
+            }
+            .into(),
+            predicate: NamedNode {
+                iri: "http://example.com/markup",
+            }
+            .into(),
+            object: Literal::Typed {
+                value: "a & b",
+                datatype: NamedNode {
+                    iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#XMLLiteral",
+                },
+            }
+            .into(),
+        })
+        .unwrap();
+    assert!(formatter.finish().is_err());
+}
+
+#[cfg(test)]
+const TEST_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+#[cfg(test)]
+const TEST_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+#[cfg(test)]
+const TEST_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+
+#[cfg(test)]
+fn format_collection(triples: &[Triple<'_>]) -> String {
+    let mut formatter = RdfXmlFormatter::new(Vec::default())
+        .unwrap()
+        .with_collection_abbreviation(true);
+    for triple in triples {
+        formatter.format(triple).unwrap();
+    }
+    String::from_utf8(formatter.finish().unwrap()).unwrap()
+}
+
+#[cfg(test)]
+fn cell(id: &'static str, predicate: &'static str, object: Term<'static>) -> Triple<'static> {
+    Triple {
+        subject: BlankNode { id }.into(),
+        predicate: NamedNode { iri: predicate }.into(),
+        object,
+    }
+}
+
+#[cfg(test)]
+fn items_head(object: Term<'static>) -> Triple<'static> {
+    Triple {
+        subject: NamedNode {
+            iri: "http://example.com/s",
+        }
+        .into(),
+        predicate: NamedNode {
+            iri: "http://example.com/items",
+        }
+        .into(),
+        object,
+    }
+}
+
+#[test]
+fn test_collection_is_collapsed() {
+    let xml = format_collection(&[
+        items_head(BlankNode { id: "l1" }.into()),
+        cell("l1", TEST_FIRST, NamedNode { iri: "http://example.com/a" }.into()),
+        cell("l1", TEST_REST, BlankNode { id: "l2" }.into()),
+        cell("l2", TEST_FIRST, NamedNode { iri: "http://example.com/b" }.into()),
+        cell("l2", TEST_REST, NamedNode { iri: TEST_NIL }.into()),
+    ]);
+    assert!(xml.contains("rdf:parseType=\"Collection\""));
+    assert!(xml.contains("rdf:about=\"http://example.com/a\""));
+    assert!(xml.contains("rdf:about=\"http://example.com/b\""));
+    assert!(!xml.contains("<first"));
+    assert!(!xml.contains("l1"));
+}
+
+#[test]
+fn test_branching_chain_falls_back() {
+    let xml = format_collection(&[
+        items_head(BlankNode { id: "l1" }.into()),
+        cell("l1", TEST_FIRST, NamedNode { iri: "http://example.com/a" }.into()),
+        cell("l1", TEST_FIRST, NamedNode { iri: "http://example.com/c" }.into()),
+        cell("l1", TEST_REST, NamedNode { iri: TEST_NIL }.into()),
+    ]);
+    assert!(!xml.contains("Collection"));
+    assert!(xml.contains("<first"));
+}
+
+#[test]
+fn test_shared_head_falls_back() {
+    let second_head = Triple {
+        subject: NamedNode {
+            iri: "http://example.com/s2",
+        }
+        .into(),
+        predicate: NamedNode {
+            iri: "http://example.com/items",
+        }
+        .into(),
+        object: BlankNode { id: "l1" }.into(),
+    };
+    let xml = format_collection(&[
+        items_head(BlankNode { id: "l1" }.into()),
+        second_head,
+        cell("l1", TEST_FIRST, NamedNode { iri: "http://example.com/a" }.into()),
+        cell("l1", TEST_REST, NamedNode { iri: TEST_NIL }.into()),
+    ]);
+    assert!(!xml.contains("Collection"));
+    assert!(xml.contains("<first"));
+}
+
+#[test]
+fn test_literal_member_falls_back() {
+    let xml = format_collection(&[
+        items_head(BlankNode { id: "l1" }.into()),
+        cell("l1", TEST_FIRST, Literal::Simple { value: "x" }.into()),
+        cell("l1", TEST_REST, NamedNode { iri: TEST_NIL }.into()),
+    ]);
+    assert!(!xml.contains("Collection"));
+    assert!(xml.contains("<first"));
+}
+
+#[test]
+fn test_cyclic_chain_falls_back() {
+    let xml = format_collection(&[
+        items_head(BlankNode { id: "l1" }.into()),
+        cell("l1", TEST_FIRST, NamedNode { iri: "http://example.com/a" }.into()),
+        cell("l1", TEST_REST, BlankNode { id: "l2" }.into()),
+        cell("l2", TEST_FIRST, NamedNode { iri: "http://example.com/b" }.into()),
+        cell("l2", TEST_REST, BlankNode { id: "l1" }.into()),
+    ]);
+    assert!(!xml.contains("Collection"));
+    assert!(xml.contains("<first"));
+}
+
+#[test]
+fn test_prefix_hoisting() {
+    let mut formatter = RdfXmlFormatter::with_prefixes(
+        Vec::default(),
+        vec![("schema".to_owned(), "http://schema.org/".to_owned())],
+    )
+    .unwrap();
+    formatter
+        .format(&Triple {
+            subject: NamedNode {
+                iri: "http://example.com/foo",
+            }
+            .into(),
+            predicate: NamedNode {
+                iri: "http://schema.org/name",
+            }
+            .into(),
+            object: Literal::Simple { value: "Alice" }.into(),
+        })
+        .unwrap();
+    formatter
+        .format(&Triple {
+            subject: NamedNode {
+                iri: "http://example.com/foo",
+            }
+            .into(),
+            predicate: NamedNode {
+                iri: "http://example.com/other/p",
+            }
+            .into(),
+            object: Literal::Simple { value: "x" }.into(),
+        })
+        .unwrap();
+    let xml = String::from_utf8(formatter.finish().unwrap()).unwrap();
+
+    // The registered binding is declared exactly once, on the root element.
+    assert_eq!(
+        xml.matches("xmlns:schema=\"http://schema.org/\"").count(),
+        1
+    );
+    // A registered predicate is a qualified name with no per-element redeclaration.
+    assert!(xml.contains("<schema:name>"));
+    // An unregistered namespace still falls back to an inline declaration.
+    assert!(xml.contains("xmlns=\"http://example.com/other/\""));
+}
@@ -0,0 +1,14 @@
+//! Implementation of a [RDF/XML](https://www.w3.org/TR/rdf-syntax-grammar/) formatter.
+//!
+//! It is based on the streaming APIs of [Rio](https://github.com/oxigraph/rio).
+
+mod error;
+mod formatter;
+mod model;
+mod utils;
+
+pub use error::RdfXmlError;
+pub use formatter::RdfXmlFormatter;
+
+#[cfg(feature = "async-tokio")]
+pub use formatter::AsyncRdfXmlFormatter;
@@ -0,0 +1,74 @@
+#![cfg(feature = "async-tokio")]
+
+use rio_api::formatter::TriplesFormatter;
+use rio_api::model::*;
+use rio_xml::{AsyncRdfXmlFormatter, RdfXmlFormatter};
+
+fn triples() -> Vec<Triple<'static>> {
+    vec![
+        Triple {
+            subject: NamedNode {
+                iri: "http://example.com/foo",
+            }
+            .into(),
+            predicate: NamedNode {
+                iri: "http://www.w3.org/1999/02/22-rdf-syntax-ns#type",
+            }
+            .into(),
+            object: NamedNode {
+                iri: "http://schema.org/Person",
+            }
+            .into(),
+        },
+        Triple {
+            subject: NamedNode {
+                iri: "http://example.com/foo",
+            }
+            .into(),
+            predicate: NamedNode {
+                iri: "http://schema.org/name",
+            }
+            .into(),
+            object: Literal::Simple { value: "Foo" }.into(),
+        },
+    ]
+}
+
+fn sync_output() -> Vec<u8> {
+    let mut formatter = RdfXmlFormatter::new(Vec::default()).unwrap();
+    for triple in &triples() {
+        formatter.format(triple).unwrap();
+    }
+    formatter.finish().unwrap()
+}
+
+#[tokio::test]
+async fn async_matches_sync() {
+    let mut formatter = AsyncRdfXmlFormatter::new(Vec::new()).await.unwrap();
+    for triple in &triples() {
+        formatter.format(triple).await.unwrap();
+    }
+    let async_output = formatter.finish().await.unwrap();
+    assert_eq!(async_output, sync_output());
+}
+
+#[tokio::test]
+async fn async_with_prefixes_matches_sync() {
+    let prefixes = vec![("schema".to_owned(), "http://schema.org/".to_owned())];
+
+    let mut sync = RdfXmlFormatter::with_prefixes(Vec::default(), prefixes.clone()).unwrap();
+    for triple in &triples() {
+        sync.format(triple).unwrap();
+    }
+    let sync_output = sync.finish().unwrap();
+
+    let mut formatter = AsyncRdfXmlFormatter::with_prefixes(Vec::new(), prefixes)
+        .await
+        .unwrap();
+    for triple in &triples() {
+        formatter.format(triple).await.unwrap();
+    }
+    let async_output = formatter.finish().await.unwrap();
+
+    assert_eq!(async_output, sync_output);
+}